@@ -1,5 +1,10 @@
+use std::ops::RangeInclusive;
+
 use druid::widget::prelude::*;
-use druid::Data;
+use druid::widget::Axis;
+use druid::{Data, Point, Rect, UnitPoint, WidgetPod};
+
+use crate::length::Length;
 
 /// A widget that changes size dynamically; the dynamic analogue to [`SizedBox`].
 ///
@@ -10,80 +15,190 @@ use druid::Data;
 ///
 /// [`SizedBox`]: druid::widget::SizedBox
 pub struct AspectRatioBox<T> {
-    inner: Option<Box<dyn Widget<T>>>,
+    inner: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
     ratio: f64,
+    width_range: (f64, f64),
+    height_range: (f64, f64),
+    width_ratio: Option<f64>,
+    height_ratio: Option<f64>,
+    width: Option<Length>,
+    height: Option<Length>,
+    alignment: UnitPoint,
 }
 
 impl<T> AspectRatioBox<T> {
     /// Create container with child, and both width and height not set.
     pub fn new(inner: impl Widget<T> + 'static, ratio: f64) -> Self {
         Self {
-            inner: Some(Box::new(inner)),
+            inner: Some(WidgetPod::new(Box::new(inner))),
             ratio,
+            width_range: (0.0, f64::INFINITY),
+            height_range: (0.0, f64::INFINITY),
+            width_ratio: None,
+            height_ratio: None,
+            width: None,
+            height: None,
+            alignment: UnitPoint::CENTER,
         }
     }
 
     /// Create container without child, and the ratio set to 1.0.
-    fn empty() -> Self {
+    ///
+    /// A childless box sizes itself as a fraction of the parent's box
+    /// constraints; see [`with_width_ratio`] and [`with_height_ratio`].
+    ///
+    /// [`with_width_ratio`]: AspectRatioBox::with_width_ratio
+    /// [`with_height_ratio`]: AspectRatioBox::with_height_ratio
+    pub fn empty() -> Self {
         Self {
             inner: None,
             ratio: 1.0,
+            width_range: (0.0, f64::INFINITY),
+            height_range: (0.0, f64::INFINITY),
+            width_ratio: None,
+            height_ratio: None,
+            width: None,
+            height: None,
+            alignment: UnitPoint::CENTER,
         }
     }
 
+    /// Builder-style method for bounding the box's width to a range.
+    ///
+    /// The laid-out width is clamped into `min..=max` after the aspect ratio
+    /// has been applied; when the axes are tied by the ratio, the height is then
+    /// re-derived from the ratio so the ratio invariant is preserved. When both
+    /// axes are sized independently, each is clamped on its own. Defaults to
+    /// `0.0..=f64::INFINITY`.
+    pub fn with_width_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.width_range = validated_range(range);
+        self
+    }
+
+    /// Builder-style method for bounding the box's height to a range.
+    ///
+    /// The laid-out height is clamped into `min..=max` after the aspect ratio
+    /// has been applied; when the axes are tied by the ratio, the width is then
+    /// re-derived from the ratio so the ratio invariant is preserved. When both
+    /// axes are sized independently, each is clamped on its own. Defaults to
+    /// `0.0..=f64::INFINITY`.
+    pub fn with_height_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.height_range = validated_range(range);
+        self
+    }
+
     /// Builder-style method for setting the ratio.
     ///
-    /// The ratio has to be a value between 0 and 1, excluding 0. It will be clamped
-    /// to those values if they exceed the bounds. If the ratio is 0, then the ratio
-    /// will become 1.
-    fn with_ratio(mut self, mut ratio: f64) -> Self {
-        ratio = f64::clamp(0.0, 1.0, ratio);
-        if ratio == 0.0 {
-            ratio = 1.0;
-        }
-        self.ratio = ratio;
+    /// The ratio is `width / height`, so values above 1.0 make a landscape box
+    /// and values below 1.0 a portrait one. A non-positive ratio is meaningless
+    /// and falls back to 1.0.
+    pub fn with_ratio(mut self, ratio: f64) -> Self {
+        self.ratio = if ratio > 0.0 { ratio } else { 1.0 };
+        self
+    }
+
+    /// Builder-style method for sizing the box's width as a fraction of the
+    /// parent's max width.
+    ///
+    /// `None` (the default) passes the parent's width constraint through. When
+    /// only one of the width/height ratios is set, the other dimension is
+    /// derived from it via the aspect ratio.
+    pub fn with_width_ratio(mut self, width_ratio: f64) -> Self {
+        self.width_ratio = Some(width_ratio);
+        self
+    }
+
+    /// Builder-style method for sizing the box's height as a fraction of the
+    /// parent's max height.
+    ///
+    /// `None` (the default) passes the parent's height constraint through. When
+    /// only one of the width/height ratios is set, the other dimension is
+    /// derived from it via the aspect ratio.
+    pub fn with_height_ratio(mut self, height_ratio: f64) -> Self {
+        self.height_ratio = Some(height_ratio);
+        self
+    }
+
+    /// Builder-style method for sizing the box's width with a [`Length`].
+    ///
+    /// This overrides the fraction/aspect-ratio sizing on the width axis: the
+    /// [`Length`] is resolved against the incoming constraints. When only one of
+    /// the width/height lengths is set, the other dimension is derived from it
+    /// via the aspect ratio.
+    pub fn with_width(mut self, width: Length) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Builder-style method for sizing the box's height with a [`Length`].
+    ///
+    /// This overrides the fraction/aspect-ratio sizing on the height axis: the
+    /// [`Length`] is resolved against the incoming constraints. When only one of
+    /// the width/height lengths is set, the other dimension is derived from it
+    /// via the aspect ratio.
+    pub fn with_height(mut self, height: Length) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Builder-style method for aligning the ratio-constrained child within the
+    /// available space.
+    ///
+    /// When the box has a child and no range or length is set, the widget
+    /// reports the parent's full `bc.max()` as its own size (the child's extent
+    /// on an axis the parent leaves unbounded), so for non-matching ratios the
+    /// child is smaller than the widget; this positions the child within the
+    /// leftover space. Otherwise the widget reports the ratio-corrected size,
+    /// the child fills it and the alignment has no effect. Defaults to
+    /// [`UnitPoint::CENTER`].
+    pub fn with_alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
         self
     }
 
     /// Set the ratio of the box.
     ///
-    /// The ratio has to be a value between 0 and 1, excluding 0. It will be clamped
-    /// to those values if they exceed the bounds. If the ratio is 0, then the ratio
-    /// will become 1.
-    pub fn set_ratio(&mut self, mut ratio: f64) {
-        ratio = f64::clamp(0.0, 1.0, ratio);
-        if ratio == 0.0 {
-            ratio = 1.0;
-        }
-        self.ratio = ratio;
-    }
-
-    // /// Determine the constraints that will be used for inner widget.
-    // fn inner_constraints(&self, bc: &BoxConstraints) -> BoxConstraints {
-    //     // if we have a width/height, multiply it by bc.max to get new width/height
-    //     // of widget and clamp on that value
-    //     // if we don't have width/height, box constraints stay the same
-    //     let (min_width, max_width) = match self.width_ratio {
-    //         Some(width) => {
-    //             let w = width * bc.max().width;
-    //             (w, w)
-    //         }
-    //         None => (bc.min().width, bc.max().width),
-    //     };
-
-    //     let (min_height, max_height) = match self.height_ratio {
-    //         Some(height) => {
-    //             let h = height * bc.max().height;
-    //             (h, h)
-    //         }
-    //         None => (bc.min().height, bc.max().height),
-    //     };
-
-    //     BoxConstraints::new(
-    //         Size::new(min_width, min_height),
-    //         Size::new(max_width, max_height),
-    //     )
-    // }
+    /// The ratio is `width / height`, so values above 1.0 make a landscape box
+    /// and values below 1.0 a portrait one. A non-positive ratio is meaningless
+    /// and falls back to 1.0.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = if ratio > 0.0 { ratio } else { 1.0 };
+    }
+
+    /// Resolve a [`Length`] to a concrete extent on `axis` against the box
+    /// constraints `bc`.
+    ///
+    /// `Fixed`/`Fill`/`FillPortion` resolve directly (a standalone box has no
+    /// flex siblings, so a portion simply fills); `Shrink` measures the child's
+    /// intrinsic extent by laying it out under loosened constraints, falling
+    /// back to the available space for a childless box. The result is clamped
+    /// into the axis constraints so a `Fixed(n)` larger than the parent cannot
+    /// hand the child an overflowing box.
+    fn resolve_length(
+        &mut self,
+        length: Length,
+        axis: Axis,
+        bc: &BoxConstraints,
+        ctx: &mut LayoutCtx,
+        data: &T,
+        env: &Env,
+    ) -> f64
+    where
+        T: Data,
+    {
+        let min = axis.major(bc.min());
+        let max = axis.major(bc.max());
+        let extent = match length {
+            Length::Shrink => match self.inner.as_mut() {
+                Some(inner) => axis.major(inner.layout(ctx, &bc.loosen(), data, env)),
+                None => max,
+            },
+            // `Fixed`/`Fill` resolve directly; a standalone box has no sibling
+            // portions to weigh against, so `FillPortion` simply fills.
+            other => other.resolve(max).unwrap_or(max),
+        };
+        extent.clamp(min, max)
+    }
 }
 
 impl<T: Data> Widget<T> for AspectRatioBox<T> {
@@ -99,59 +214,93 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
         if let Some(ref mut inner) = self.inner {
-            inner.update(ctx, old_data, data, env);
+            inner.update(ctx, data, env);
         }
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("DynamicSizedBox");
 
-        // let mut bc = bc.loosen();
-        // dbg!(&bc);
+        let parent_max = bc.max();
         let (mut width, mut height) = (bc.max().width, bc.max().height);
-        // this means we want the height to be the larger value
-        // height and width are the max box constraints
-        // if ratio is below 1 then the height of the box has the be the largest dimension
-        // the width will then be a height * ratio
-        let bc = if self.ratio < 1.0 {
-            if (height >= width && height * self.ratio <= width) || width > height {
-                width = height * self.ratio;
-            } else if height >= width && height * self.ratio > width {
-                height = width / self.ratio;
-            }
-            BoxConstraints::tight(Size::new(width, height))
-        }
-        // this means we want the width to be the larger value
-        // if the ratio is above one then the width of the box has to be the largest dimension
-        // the height will then be the width / ratio
-        else if self.ratio > 1.0 {
-            if width > height && height * self.ratio < width {
-                width = height * self.ratio;
-                // height = width / self.ratio;
-            } else if (width > height && height * self.ratio > width) || height > width {
-                height = width / self.ratio;
+        // Whether the two axes are tied together by the ratio. When both axes are
+        // sized independently (both lengths or both fraction ratios set) the box
+        // is not ratio-locked and each axis is range-clamped on its own.
+        let ratio_locked;
+
+        // If either axis is sized with a `Length`, resolve it against the
+        // incoming constraints; a `None` axis is derived from the set one via the
+        // aspect ratio. This takes precedence over the fraction/ratio sizing.
+        if self.width.is_some() || self.height.is_some() {
+            match (self.width, self.height) {
+                (Some(wl), Some(hl)) => {
+                    width = self.resolve_length(wl, Axis::Horizontal, bc, ctx, data, env);
+                    height = self.resolve_length(hl, Axis::Vertical, bc, ctx, data, env);
+                    ratio_locked = false;
+                }
+                (Some(wl), None) => {
+                    width = self.resolve_length(wl, Axis::Horizontal, bc, ctx, data, env);
+                    height = width / self.ratio;
+                    ratio_locked = true;
+                }
+                (None, Some(hl)) => {
+                    height = self.resolve_length(hl, Axis::Vertical, bc, ctx, data, env);
+                    width = height * self.ratio;
+                    ratio_locked = true;
+                }
+                (None, None) => unreachable!(),
             }
-            // dbg!(height, width);
-            BoxConstraints::tight(Size::new(width, height))
         }
-        // the aspect ratio is 1:1 which means we want a square
-        // we take the minimum between the width and height and constrain to that min
+        // If either fraction-of-parent ratio is set, resolve the box size from
+        // those directly; a `None` dimension is derived from the set one via the
+        // aspect ratio rather than falling to zero. When neither is set we fall
+        // through to the plain aspect-ratio logic below against `bc.max()`.
+        else if self.width_ratio.is_some() || self.height_ratio.is_some() {
+            let (w, h, locked) =
+                fraction_fit(self.ratio, self.width_ratio, self.height_ratio, parent_max);
+            width = w;
+            height = h;
+            ratio_locked = locked;
+        }
+        // Otherwise size the largest box of the given aspect ratio that fits the
+        // parent's max constraints.
         else {
-            let min = width.min(height);
-            BoxConstraints::tight(Size::new(min, min))
-        };
-        dbg!(&bc);
+            let (w, h) = aspect_ratio_fit(self.ratio, width, height);
+            width = w;
+            height = h;
+            ratio_locked = true;
+        }
+
+        let (width, height) = clamp_ranges(
+            self.ratio,
+            ratio_locked,
+            width,
+            height,
+            self.width_range,
+            self.height_range,
+        );
+        let bc = BoxConstraints::tight(Size::new(width, height));
 
-        // let inner_bc = self.inner_constraints(&bc);
-        let size = match self.inner.as_mut() {
+        // Lay the child out tight to the ratio-corrected box.
+        let child_size = match self.inner.as_mut() {
             Some(inner) => inner.layout(ctx, &bc, data, env),
             None => bc.max(),
         };
-        // let size = bc.max();
+
+        // Only expand to the parent's full space (and align the child in the
+        // leftover) when the size is purely ratio-driven. Once a range or length
+        // caps an axis, reporting the parent max would defeat that cap, so we
+        // report the ratio-corrected size instead and the child fills it.
+        let constrained = self.width.is_some()
+            || self.height.is_some()
+            || self.width_range != (0.0, f64::INFINITY)
+            || self.height_range != (0.0, f64::INFINITY);
+        let claim_parent = self.inner.is_some() && !constrained;
+        let (size, origin) = aligned_box(parent_max, child_size, claim_parent, self.alignment);
         if let Some(ref mut inner) = self.inner {
-            inner.layout(ctx, &bc, data, env);
+            inner.set_origin(ctx, origin);
         }
 
         if size.width.is_infinite() {
@@ -172,6 +321,270 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
     }
 
     fn id(&self) -> Option<WidgetId> {
-        self.inner.as_ref().and_then(|inner| inner.id())
+        self.inner.as_ref().map(|inner| inner.id())
+    }
+}
+
+/// Validate a user-supplied dimension range, guarding against the reversed and
+/// NaN bounds that would otherwise make `f64::clamp` panic during layout.
+fn validated_range(range: RangeInclusive<f64>) -> (f64, f64) {
+    let (start, end) = (*range.start(), *range.end());
+    assert!(
+        !start.is_nan() && !end.is_nan(),
+        "AspectRatioBox dimension range must not contain NaN"
+    );
+    assert!(
+        start <= end,
+        "AspectRatioBox dimension range start ({start}) must not exceed end ({end})"
+    );
+    (start, end)
+}
+
+/// Fit the largest box of aspect `ratio` (`width / height`) inside the
+/// `width`/`height` maximums, returning the corrected `(width, height)`.
+fn aspect_ratio_fit(ratio: f64, mut width: f64, mut height: f64) -> (f64, f64) {
+    if ratio < 1.0 {
+        // Portrait: height is the leading dimension.
+        if (height >= width && height * ratio <= width) || width > height {
+            width = height * ratio;
+        } else {
+            height = width / ratio;
+        }
+    } else if ratio > 1.0 {
+        // Landscape: width is the leading dimension. Deriving height from width
+        // is correct whenever width is the binding limit, including the square
+        // `width == height` case which the old strict comparisons left untouched.
+        if width / ratio <= height {
+            height = width / ratio;
+        } else {
+            width = height * ratio;
+        }
+    } else {
+        let min = width.min(height);
+        width = min;
+        height = min;
+    }
+    (width, height)
+}
+
+/// Size a childless box as a fraction of the parent's max constraints.
+///
+/// A `None` dimension is derived from the set one via the ratio rather than
+/// falling to zero. When both fractions are set the axes are independent, so the
+/// returned flag reports the box is not ratio-locked.
+fn fraction_fit(
+    ratio: f64,
+    width_ratio: Option<f64>,
+    height_ratio: Option<f64>,
+    parent_max: Size,
+) -> (f64, f64, bool) {
+    match (width_ratio, height_ratio) {
+        (Some(wr), Some(hr)) => (wr * parent_max.width, hr * parent_max.height, false),
+        (Some(wr), None) => {
+            let width = wr * parent_max.width;
+            (width, width / ratio, true)
+        }
+        (None, Some(hr)) => {
+            let height = hr * parent_max.height;
+            (height * ratio, height, true)
+        }
+        (None, None) => unreachable!(),
+    }
+}
+
+/// Clamp `width`/`height` into their configured ranges.
+///
+/// When the box is ratio-locked the two axes are clamped jointly: the width is
+/// confined to the intersection of its own range and the range implied by the
+/// height range through the ratio, so both ranges hold simultaneously (or, for
+/// incompatible ranges, the lower bounds win). Otherwise each axis is clamped
+/// independently.
+fn clamp_ranges(
+    ratio: f64,
+    ratio_locked: bool,
+    mut width: f64,
+    mut height: f64,
+    width_range: (f64, f64),
+    height_range: (f64, f64),
+) -> (f64, f64) {
+    let (wmin, wmax) = width_range;
+    let (hmin, hmax) = height_range;
+    if ratio_locked {
+        let lower = wmin.max(hmin * ratio);
+        let upper = wmax.min(hmax * ratio);
+        width = if lower <= upper {
+            width.clamp(lower, upper)
+        } else {
+            lower
+        };
+        height = width / ratio;
+    } else {
+        width = width.clamp(wmin, wmax);
+        height = height.clamp(hmin, hmax);
+    }
+    (width, height)
+}
+
+/// Compute the widget's reported size and the child's origin within it.
+///
+/// When `claim_parent` is set the widget claims the parent's full available
+/// space (falling back to the child's extent on any axis the parent left
+/// unbounded) and the child is aligned within the leftover space. Otherwise the
+/// widget reports the child's (ratio-corrected) size and pins the origin at
+/// zero — this is the case for childless boxes and for boxes whose size is
+/// already capped by a range or length.
+fn aligned_box(
+    parent_max: Size,
+    child_size: Size,
+    claim_parent: bool,
+    alignment: UnitPoint,
+) -> (Size, Point) {
+    let size = if claim_parent {
+        Size::new(
+            if parent_max.width.is_finite() {
+                parent_max.width
+            } else {
+                child_size.width
+            },
+            if parent_max.height.is_finite() {
+                parent_max.height
+            } else {
+                child_size.height
+            },
+        )
+    } else {
+        child_size
+    };
+
+    let extra = Size::new(
+        (size.width - child_size.width).max(0.0),
+        (size.height - child_size.height).max(0.0),
+    );
+    let origin = alignment.resolve(Rect::from_origin_size(Point::ORIGIN, extra));
+    (size, origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centers_child_in_leftover_space() {
+        // A 2:1 child within a 400x400 parent: the widget claims the full parent
+        // size and the child is centered in the vertical slack.
+        let parent = Size::new(400.0, 400.0);
+        let child = Size::new(400.0, 200.0);
+        let (size, origin) = aligned_box(parent, child, true, UnitPoint::CENTER);
+        assert_eq!(size, parent);
+        assert_eq!(origin, Point::new(0.0, 100.0));
+    }
+
+    #[test]
+    fn childless_box_pins_origin() {
+        let ratio_box = Size::new(120.0, 80.0);
+        let (size, origin) = aligned_box(ratio_box, ratio_box, false, UnitPoint::CENTER);
+        assert_eq!(size, ratio_box);
+        assert_eq!(origin, Point::ORIGIN);
+    }
+
+    #[test]
+    fn capped_box_reports_child_size_not_parent() {
+        // When a range/length caps the size (`claim_parent` is false) the widget
+        // reports the capped child size so a measuring parent still sees the cap.
+        let parent = Size::new(1200.0, 1200.0);
+        let capped = Size::new(800.0, 450.0);
+        let (size, origin) = aligned_box(parent, capped, false, UnitPoint::CENTER);
+        assert_eq!(size, capped);
+        assert_eq!(origin, Point::ORIGIN);
+    }
+
+    #[test]
+    fn aspect_ratio_fit_shrinks_to_binding_axis() {
+        // Landscape ratio inside a square: width binds, height is derived.
+        assert_eq!(aspect_ratio_fit(2.0, 400.0, 400.0), (400.0, 200.0));
+        // Portrait ratio inside a square: height binds, width is derived.
+        assert_eq!(aspect_ratio_fit(0.5, 400.0, 400.0), (200.0, 400.0));
+        // Landscape ratio where height is the tighter bound.
+        assert_eq!(aspect_ratio_fit(2.0, 400.0, 100.0), (200.0, 100.0));
+    }
+
+    #[test]
+    fn range_clamp_caps_width_and_preserves_ratio() {
+        // 16:9, capped at 800px wide: height follows from the ratio.
+        let (w, h) = clamp_ranges(
+            16.0 / 9.0,
+            true,
+            1600.0,
+            900.0,
+            (0.0, 800.0),
+            (0.0, f64::INFINITY),
+        );
+        assert_eq!(w, 800.0);
+        assert!((h - 450.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn range_clamp_keeps_both_bounds_when_compatible() {
+        // width <= 800 and height >= 400 are jointly satisfiable at 16:9:
+        // height 450 already satisfies the floor, width stays at the 800 cap.
+        let (w, h) = clamp_ranges(
+            16.0 / 9.0,
+            true,
+            1600.0,
+            900.0,
+            (0.0, 800.0),
+            (400.0, f64::INFINITY),
+        );
+        assert_eq!(w, 800.0);
+        assert!((h - 450.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn range_clamp_unlocked_axes_are_independent() {
+        let (w, h) = clamp_ranges(
+            1.0,
+            false,
+            1000.0,
+            1000.0,
+            (0.0, 800.0),
+            (0.0, 600.0),
+        );
+        assert_eq!(w, 800.0);
+        assert_eq!(h, 600.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reversed_range_is_rejected() {
+        validated_range(800.0..=0.0);
+    }
+
+    #[test]
+    fn fraction_width_derives_height_from_ratio() {
+        // `empty().with_ratio(1.6).with_width_ratio(0.5)` against a 1000x1000
+        // parent: half the parent's width at 16:10.
+        let parent = Size::new(1000.0, 1000.0);
+        let (w, h, locked) = fraction_fit(1.6, Some(0.5), None, parent);
+        assert_eq!(w, 500.0);
+        assert!((h - 312.5).abs() < 1e-9);
+        assert!(locked);
+    }
+
+    #[test]
+    fn fraction_height_derives_width_from_ratio() {
+        let parent = Size::new(1000.0, 800.0);
+        let (w, h, locked) = fraction_fit(2.0, None, Some(0.5), parent);
+        assert_eq!(h, 400.0);
+        assert_eq!(w, 800.0);
+        assert!(locked);
+    }
+
+    #[test]
+    fn fraction_both_axes_are_independent() {
+        let parent = Size::new(1000.0, 600.0);
+        let (w, h, locked) = fraction_fit(1.0, Some(0.5), Some(0.25), parent);
+        assert_eq!(w, 500.0);
+        assert_eq!(h, 150.0);
+        assert!(!locked);
     }
 }
@@ -0,0 +1,142 @@
+use druid::widget::prelude::*;
+use druid::Data;
+
+use crate::length::Length;
+
+/// A box that sizes its child using a [`Length`] per axis.
+///
+/// This is the sibling of [`AspectRatioBox`] for cases where the two axes are
+/// sized independently rather than tied together by a ratio. Each axis accepts
+/// the full [`Length`] vocabulary: a fixed pixel amount, [`Fill`], a weighted
+/// [`FillPortion`], or [`Shrink`] to the child's intrinsic extent.
+///
+/// A `FlexSizedBox` resolves [`FillPortion`] like [`Fill`]: it does not weigh
+/// its portion against siblings, and no widget in this crate divides space by
+/// portion. [`Length::fill_portion`] reports the weight.
+///
+/// [`AspectRatioBox`]: crate::aspect_ratio_box::AspectRatioBox
+/// [`Fill`]: Length::Fill
+/// [`FillPortion`]: Length::FillPortion
+/// [`Shrink`]: Length::Shrink
+pub struct FlexSizedBox<T> {
+    inner: Box<dyn Widget<T>>,
+    width: Length,
+    height: Length,
+}
+
+impl<T> FlexSizedBox<T> {
+    /// Create a box wrapping `inner`, filling both axes by default.
+    pub fn new(inner: impl Widget<T> + 'static) -> Self {
+        Self {
+            inner: Box::new(inner),
+            width: Length::Fill,
+            height: Length::Fill,
+        }
+    }
+
+    /// Builder-style method for setting the width [`Length`].
+    pub fn with_width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Builder-style method for setting the height [`Length`].
+    pub fn with_height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+/// The `(min, max)` axis bounds to hand the child, and whether the axis should
+/// finally collapse to the child's returned extent (i.e. was [`Length::Shrink`]).
+fn axis_bounds(length: Length, min: f64, max: f64) -> (f64, f64, bool) {
+    match length {
+        Length::Shrink => (0.0, max, true),
+        // `Fixed`/`Fill`/`FillPortion` all resolve to a concrete extent here;
+        // `FillPortion` has no siblings to weigh against in a standalone box.
+        other => {
+            let extent = other.resolve(max).unwrap_or(max).clamp(min, max);
+            (extent, extent, false)
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for FlexSizedBox<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, old_data, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("FlexSizedBox");
+
+        let (min_width, max_width, shrink_width) =
+            axis_bounds(self.width, bc.min().width, bc.max().width);
+        let (min_height, max_height, shrink_height) =
+            axis_bounds(self.height, bc.min().height, bc.max().height);
+
+        let child_bc = BoxConstraints::new(
+            Size::new(min_width, min_height),
+            Size::new(max_width, max_height),
+        );
+        let child_size = self.inner.layout(ctx, &child_bc, data, env);
+
+        // Shrinking axes collapse to the child's intrinsic extent; the others
+        // take the resolved extent.
+        let size = Size::new(
+            if shrink_width { child_size.width } else { max_width },
+            if shrink_height { child_size.height } else { max_height },
+        );
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.inner.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_length_clamps_into_constraints() {
+        // A fixed extent within the bounds is used as-is...
+        assert_eq!(axis_bounds(Length::Fixed(200.0), 0.0, 400.0), (200.0, 200.0, false));
+        // ...and one larger than the parent is clamped instead of overflowing.
+        assert_eq!(axis_bounds(Length::Fixed(900.0), 0.0, 400.0), (400.0, 400.0, false));
+    }
+
+    #[test]
+    fn fill_and_portion_take_available_space() {
+        assert_eq!(axis_bounds(Length::Fill, 0.0, 400.0), (400.0, 400.0, false));
+        // A standalone box has no siblings, so a portion fills all of it.
+        assert_eq!(axis_bounds(Length::FillPortion(2), 0.0, 400.0), (400.0, 400.0, false));
+    }
+
+    #[test]
+    fn shrink_loosens_and_flags_for_intrinsic_size() {
+        assert_eq!(axis_bounds(Length::Shrink, 10.0, 400.0), (0.0, 400.0, true));
+    }
+
+    #[test]
+    fn fill_portions_sum_as_weights() {
+        assert_eq!(Length::Fill.fill_portion(), 1);
+        assert_eq!(Length::FillPortion(3).fill_portion(), 3);
+        assert_eq!(Length::Fixed(10.0).fill_portion(), 0);
+        assert_eq!(Length::Shrink.fill_portion(), 0);
+        assert!(Length::Fill.is_fill());
+        assert!(!Length::Shrink.is_fill());
+    }
+}
@@ -0,0 +1,133 @@
+use druid::widget::prelude::*;
+use druid::widget::Axis;
+use druid::Data;
+
+/// A widget that sizes its child to the child's intrinsic extent along one axis.
+///
+/// Normally a child expands to fill the constraints it is given, which is
+/// unhelpful when those constraints are unbounded on the axis of interest.
+/// `Intrinsic` measures the child's natural content size on its [`Axis`] and
+/// then lays the child out tight to that measured extent (clamped into the
+/// incoming constraints), leaving the cross axis untouched.
+///
+/// Use [`Intrinsic::width`] for horizontal (the `IntrinsicWidth` equivalent) and
+/// [`Intrinsic::height`] for vertical (the `IntrinsicHeight` equivalent).
+pub struct Intrinsic<T> {
+    inner: Box<dyn Widget<T>>,
+    axis: Axis,
+}
+
+impl<T> Intrinsic<T> {
+    /// Create a widget that sizes `inner` to its intrinsic extent on `axis`.
+    pub fn new(inner: impl Widget<T> + 'static, axis: Axis) -> Self {
+        Self {
+            inner: Box::new(inner),
+            axis,
+        }
+    }
+
+    /// Size the child to its intrinsic width, letting it expand freely on the
+    /// vertical axis.
+    pub fn width(inner: impl Widget<T> + 'static) -> Self {
+        Self::new(inner, Axis::Horizontal)
+    }
+
+    /// Size the child to its intrinsic height, letting it expand freely on the
+    /// horizontal axis.
+    pub fn height(inner: impl Widget<T> + 'static) -> Self {
+        Self::new(inner, Axis::Vertical)
+    }
+}
+
+impl<T: Data> Widget<T> for Intrinsic<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, old_data, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Intrinsic");
+
+        // Measurement pass: loosen the measured axis to unbounded while keeping
+        // the cross-axis constraint, so the child reports its natural extent.
+        let measure_bc = bc
+            .shrink_min_to(self.axis, 0.0)
+            .shrink_max_to(self.axis, f64::INFINITY);
+        let intrinsic = self.axis.major(self.inner.layout(ctx, &measure_bc, data, env));
+
+        let (min, max) = (self.axis.major(bc.min()), self.axis.major(bc.max()));
+        let extent = intrinsic_extent(intrinsic, min, max);
+
+        // Real pass: lay the child out tight on the measured axis, leaving the
+        // cross-axis constraint as the parent provided it.
+        let real_bc = bc.shrink_max_to(self.axis, extent).shrink_min_to(self.axis, extent);
+        self.inner.layout(ctx, &real_bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.inner.id()
+    }
+}
+
+/// Clamp a measured intrinsic extent into the `[min, max]` the parent allows.
+///
+/// An infinite measurement means the child could not be bounded on this axis;
+/// fall back to the parent's maximum and warn rather than propagate infinity.
+fn intrinsic_extent(intrinsic: f64, min: f64, max: f64) -> f64 {
+    if intrinsic.is_infinite() {
+        log::warn!("Intrinsic measurement came back infinite; falling back to bc.max().");
+        max
+    } else {
+        intrinsic.min(max).max(min)
+    }
+}
+
+/// Replace the min or max bound of a [`BoxConstraints`] on a single axis,
+/// keeping the other axis as it is.
+trait AxisConstraints {
+    fn shrink_max_to(&self, axis: Axis, extent: f64) -> BoxConstraints;
+    fn shrink_min_to(&self, axis: Axis, extent: f64) -> BoxConstraints;
+}
+
+impl AxisConstraints for BoxConstraints {
+    fn shrink_max_to(&self, axis: Axis, extent: f64) -> BoxConstraints {
+        let (w, h) = axis.pack(extent, axis.minor(self.max()));
+        BoxConstraints::new(self.min(), Size::new(w, h))
+    }
+
+    fn shrink_min_to(&self, axis: Axis, extent: f64) -> BoxConstraints {
+        let (w, h) = axis.pack(extent, axis.minor(self.min()));
+        BoxConstraints::new(Size::new(w, h), self.max())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intrinsic_extent_clamps_into_constraints() {
+        // Natural extent sits inside the bounds: used as-is.
+        assert_eq!(intrinsic_extent(120.0, 0.0, 400.0), 120.0);
+        // Larger than the max: capped.
+        assert_eq!(intrinsic_extent(900.0, 0.0, 400.0), 400.0);
+        // Smaller than the min: floored.
+        assert_eq!(intrinsic_extent(50.0, 100.0, 400.0), 100.0);
+    }
+
+    #[test]
+    fn intrinsic_extent_falls_back_on_infinite() {
+        assert_eq!(intrinsic_extent(f64::INFINITY, 0.0, 400.0), 400.0);
+    }
+}
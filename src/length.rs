@@ -0,0 +1,56 @@
+/// A flexible length for a single layout axis, modelled on iced's `Length`.
+///
+/// This gives the nursery's box widgets a richer sizing vocabulary than raw
+/// pixels or ratios: a length can be a fixed pixel amount, fill the available
+/// space, take a weighted share of it within a flex container, or shrink to the
+/// child's intrinsic extent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// A fixed amount of pixels.
+    Fixed(f64),
+    /// Fill all of the remaining space on the axis.
+    Fill,
+    /// Fill a weighted portion of the remaining space, relative to the sum of
+    /// all portions requested by the siblings of an enclosing flex container.
+    FillPortion(u16),
+    /// Shrink to the child's intrinsic extent on the axis.
+    Shrink,
+}
+
+impl Length {
+    /// The weight this length contributes when a flex container divides space.
+    ///
+    /// [`Fill`] counts as a single portion; [`FillPortion`] contributes its own
+    /// weight; the non-filling lengths contribute nothing.
+    ///
+    /// [`Fill`]: Length::Fill
+    /// [`FillPortion`]: Length::FillPortion
+    pub fn fill_portion(self) -> u16 {
+        match self {
+            Length::Fill => 1,
+            Length::FillPortion(portion) => portion,
+            Length::Fixed(_) | Length::Shrink => 0,
+        }
+    }
+
+    /// Whether this length wants to fill (a share of) the available space.
+    pub fn is_fill(self) -> bool {
+        self.fill_portion() > 0
+    }
+
+    /// Resolve to a concrete extent against `available`, the axis's maximum.
+    ///
+    /// Returns `None` for [`Shrink`], which can only be resolved by measuring
+    /// the child, and for [`FillPortion`], which has no sibling portions to
+    /// divide against here and must be resolved by the caller.
+    ///
+    /// [`Shrink`]: Length::Shrink
+    /// [`FillPortion`]: Length::FillPortion
+    pub fn resolve(self, available: f64) -> Option<f64> {
+        match self {
+            Length::Fixed(pixels) => Some(pixels),
+            Length::Fill => Some(available),
+            Length::FillPortion(_) | Length::Shrink => None,
+        }
+    }
+}
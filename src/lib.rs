@@ -0,0 +1,4 @@
+pub mod aspect_ratio_box;
+pub mod flex_sized_box;
+pub mod intrinsic;
+pub mod length;